@@ -51,7 +51,7 @@ impl<'a> ChangelogEntry<'a> {
         writer.write_all(b"\n\n")?;
         writer.write_all(self.details.as_bytes())?;
         writer.write_all(b"\n")?;
-        writer.write_all(b"-- ")?;
+        writer.write_all(b" -- ")?;
         writer.write_all(self.maintainer_name.as_bytes())?;
         writer.write_all(b" <")?;
         writer.write_all(self.maintainer_email.as_bytes())?;
@@ -77,6 +77,13 @@ impl<'a> Changelog<'a> {
         self.entries.push(entry)
     }
 
+    /// Obtain an iterator of entries in this changelog.
+    ///
+    /// Entries are emitted in the order they were added, which should be newest first.
+    pub fn iter_entries(&self) -> impl Iterator<Item = &ChangelogEntry<'a>> {
+        self.entries.iter()
+    }
+
     /// Serialize the changelog to a writer.
     ///
     /// Use of a buffered writer is encouraged if performance is a concern.
@@ -114,7 +121,7 @@ mod tests {
         changelog.write(&mut buf)?;
 
         let s = String::from_utf8(buf).unwrap();
-        assert_eq!(s, "mypackage (0.1) mydist; urgency=low\n\ndetails\n-- maintainer <me@example.com>  Tue, 30 Dec 2014 21:26:40 -0700\n\n");
+        assert_eq!(s, "mypackage (0.1) mydist; urgency=low\n\ndetails\n -- maintainer <me@example.com>  Tue, 30 Dec 2014 21:26:40 -0700\n\n");
 
         Ok(())
     }
@@ -5,13 +5,18 @@
 /*! Create .deb package files and their components. */
 
 use {
-    crate::{control::ControlFile, deb::DebCompression, error::Result},
+    crate::{
+        control::ControlFile,
+        deb::{DebCompression, MaintainerScript},
+        error::{DebianError, Result},
+    },
     md5::Digest,
     os_str_bytes::OsStrBytes,
-    simple_file_manifest::{FileEntry, FileManifest},
+    simple_file_manifest::{normalize_path, FileEntry, FileManifest, FileManifestError},
     std::{
+        collections::{BTreeMap, BTreeSet},
         io::{BufWriter, Cursor, Read, Write},
-        path::Path,
+        path::{Component, Path, PathBuf},
         time::SystemTime,
     },
 };
@@ -25,6 +30,9 @@ pub struct DebBuilder<'control> {
     /// Files to install as part of the package.
     install_files: FileManifest,
 
+    /// Explicit permissions of paths in the `data.tar` archive.
+    file_modes: BTreeMap<PathBuf, u32>,
+
     mtime: Option<SystemTime>,
 }
 
@@ -34,7 +42,8 @@ impl<'control> DebBuilder<'control> {
         Self {
             control_builder: ControlTarBuilder::new(control_file),
             compression: DebCompression::Gzip,
-            install_files: FileManifest::default(),
+            install_files: FileManifest::new_with_links(),
+            file_modes: BTreeMap::new(),
             mtime: None,
         }
     }
@@ -79,6 +88,18 @@ impl<'control> DebBuilder<'control> {
         Ok(self)
     }
 
+    /// Set the content of a maintainer script.
+    ///
+    /// The script is added to the `control.tar` archive and is marked executable.
+    pub fn maintainer_script(
+        mut self,
+        script: MaintainerScript,
+        entry: impl Into<FileEntry>,
+    ) -> Result<Self> {
+        self.control_builder = self.control_builder.set_maintainer_script(script, entry)?;
+        Ok(self)
+    }
+
     /// Register a file as to be installed by this package.
     ///
     /// Filenames should be relative to the filesystem root. e.g.
@@ -86,6 +107,11 @@ impl<'control> DebBuilder<'control> {
     ///
     /// The file content will be added to the `data.tar` archive and registered with
     /// the `control.tar` archive so its checksum is computed.
+    ///
+    /// If the entry is a symlink (it has a link target), a symlink entry is written
+    /// to the `data.tar` archive instead. Symlinks do not have checksums recorded.
+    /// Installing a symlink errors if `path` is already registered, as its checksum
+    /// may already be recorded.
     pub fn install_file(
         mut self,
         path: impl AsRef<Path> + Clone,
@@ -93,17 +119,89 @@ impl<'control> DebBuilder<'control> {
     ) -> Result<Self> {
         let entry = entry.into();
 
-        let data = entry.resolve_content()?;
-        let mut cursor = Cursor::new(&data);
-        self.control_builder = self
-            .control_builder
-            .add_data_file(path.clone(), &mut cursor)?;
+        if entry.link_target().is_some() {
+            let normalized = normalize_path(path.as_ref())?;
+
+            if self.install_files.has_path(&normalized) {
+                return Err(DebianError::DebSymlinkPathConflict(
+                    normalized.display().to_string(),
+                ));
+            }
+        } else {
+            let data = entry.resolve_content()?;
+            let mut cursor = Cursor::new(&data);
+            self.control_builder = self
+                .control_builder
+                .add_data_file(path.clone(), &mut cursor)?;
+        }
 
         self.install_files.add_file_entry(path, entry)?;
 
         Ok(self)
     }
 
+    /// Register a configuration file as to be installed by this package.
+    ///
+    /// This is like [Self::install_file()] except the path is also recorded in the
+    /// `conffiles` file of the `control.tar` archive. `dpkg` preserves local modifications
+    /// to these files during package upgrades.
+    ///
+    /// Errors if the entry is a symlink, as `dpkg` only accepts regular files as conffiles.
+    pub fn install_conffile(
+        mut self,
+        path: impl AsRef<Path> + Clone,
+        entry: impl Into<FileEntry>,
+    ) -> Result<Self> {
+        let entry = entry.into();
+
+        if entry.link_target().is_some() {
+            return Err(DebianError::DebConffileNotRegularFile(
+                path.as_ref().display().to_string(),
+            ));
+        }
+
+        self = self.install_file(path.clone(), entry)?;
+        self.control_builder = self.control_builder.add_conffile(path)?;
+
+        Ok(self)
+    }
+
+    /// Register a symlink as to be installed by this package.
+    ///
+    /// `path` is the path of the link relative to the filesystem root. e.g.
+    /// `usr/bin/myapp`. `target` is the path the link points to. It can be relative
+    /// to the directory containing the link or absolute. e.g. `../lib/myapp/myapp`.
+    ///
+    /// This is a convenience wrapper around [Self::install_file()].
+    pub fn install_symlink(
+        self,
+        path: impl AsRef<Path> + Clone,
+        target: impl AsRef<Path>,
+    ) -> Result<Self> {
+        let mut entry = FileEntry::new_from_data(vec![], false);
+        entry.set_link_target(target.as_ref().to_path_buf());
+
+        self.install_file(path, entry)
+    }
+
+    /// Set the permissions of a path in the `data.tar` archive.
+    ///
+    /// By default, files have mode `0755` if they are executable and `0644` otherwise
+    /// and directories have mode `0755`. This overrides the mode for a file or
+    /// directory. e.g. `set_file_mode("etc/myapp/secret.conf", 0o640)`.
+    ///
+    /// `mode` can contain the permission bits, including setuid, setgid, and sticky
+    /// bits. Modes of symlinks are always `0777`. All entries are owned by `root:root`.
+    pub fn set_file_mode(mut self, path: impl AsRef<Path>, mode: u32) -> Result<Self> {
+        if mode & !0o7777 != 0 {
+            return Err(DebianError::DebInvalidFileMode(mode));
+        }
+
+        self.file_modes.insert(normalize_path(path.as_ref())?, mode);
+
+        Ok(self)
+    }
+
     /// Write `.deb` file content to a writer.
     ///
     /// This effectively materialized the `.deb` package somewhere.
@@ -139,7 +237,12 @@ impl<'control> DebBuilder<'control> {
 
         // Third entry is a data.tar with file content.
         let mut data_writer = BufWriter::new(Vec::new());
-        write_deb_tar(&mut data_writer, &self.install_files, self.mtime())?;
+        write_deb_tar_with_modes(
+            &mut data_writer,
+            &self.install_files,
+            self.mtime(),
+            &self.file_modes,
+        )?;
         let data_tar = data_writer.into_inner().map_err(|e| e.into_error())?;
         let data_tar = self
             .compression
@@ -200,18 +303,7 @@ fn set_header_path(
     } else {
         // Else we emit a special entry to extend the filename. Who knew tar
         // files were this jank.
-        let mut header2 = tar::Header::new_gnu();
-        let name = b"././@LongLink";
-        header2.as_gnu_mut().unwrap().name[..name.len()].clone_from_slice(&name[..]);
-        header2.set_mode(0o644);
-        header2.set_uid(0);
-        header2.set_gid(0);
-        header2.set_mtime(0);
-        header2.set_size(value_bytes.len() as u64 + 1);
-        header2.set_entry_type(tar::EntryType::new(b'L'));
-        header2.set_cksum();
-        let mut data = value_bytes.chain(std::io::repeat(0).take(1));
-        builder.append(&header2, &mut data)?;
+        append_gnu_long_name(builder, b'L', value_bytes)?;
 
         let truncated_bytes = &value_bytes[0..name_buffer.len()];
         name_buffer[0..truncated_bytes.len()].copy_from_slice(truncated_bytes);
@@ -220,6 +312,54 @@ fn set_header_path(
     Ok(())
 }
 
+fn set_header_link_name(
+    builder: &mut tar::Builder<impl Write>,
+    header: &mut tar::Header,
+    target: &Path,
+) -> Result<()> {
+    // Link targets are stored verbatim. Like paths, they are subject to the 100 byte
+    // limit of the header field and need a GNU extension entry if they are longer.
+    let value = format!("{}", target.display());
+    let value_bytes = value.as_bytes();
+
+    let linkname_buffer = &mut header.as_old_mut().linkname;
+
+    if value_bytes.len() <= linkname_buffer.len() {
+        linkname_buffer[0..value_bytes.len()].copy_from_slice(value_bytes);
+    } else {
+        append_gnu_long_name(builder, b'K', value_bytes)?;
+
+        let truncated_bytes = &value_bytes[0..linkname_buffer.len()];
+        linkname_buffer[0..truncated_bytes.len()].copy_from_slice(truncated_bytes);
+    }
+
+    Ok(())
+}
+
+/// Append a GNU `././@LongLink` entry holding a long name for the entry that follows.
+///
+/// `entry_type` is `L` for a long path or `K` for a long link target.
+fn append_gnu_long_name(
+    builder: &mut tar::Builder<impl Write>,
+    entry_type: u8,
+    value_bytes: &[u8],
+) -> Result<()> {
+    let mut header = tar::Header::new_gnu();
+    let name = b"././@LongLink";
+    header.as_gnu_mut().unwrap().name[..name.len()].clone_from_slice(&name[..]);
+    header.set_mode(0o644);
+    header.set_uid(0);
+    header.set_gid(0);
+    header.set_mtime(0);
+    header.set_size(value_bytes.len() as u64 + 1);
+    header.set_entry_type(tar::EntryType::new(entry_type));
+    header.set_cksum();
+    let mut data = value_bytes.chain(std::io::repeat(0).take(1));
+    builder.append(&header, &mut data)?;
+
+    Ok(())
+}
+
 /// A builder for a `control.tar` file inside `.deb` packages.
 pub struct ControlTarBuilder<'a> {
     /// The file that will become the `control` file.
@@ -228,6 +368,8 @@ pub struct ControlTarBuilder<'a> {
     extra_files: FileManifest,
     /// Hashes of files that will be installed.
     md5sums: Vec<Vec<u8>>,
+    /// Paths of installed files that are configuration files.
+    conffiles: BTreeSet<PathBuf>,
     /// Modified time for tar archive entries.
     mtime: Option<SystemTime>,
 }
@@ -239,6 +381,7 @@ impl<'a> ControlTarBuilder<'a> {
             control: control_file,
             extra_files: FileManifest::default(),
             md5sums: vec![],
+            conffiles: BTreeSet::new(),
             mtime: None,
         }
     }
@@ -258,6 +401,43 @@ impl<'a> ControlTarBuilder<'a> {
         Ok(self)
     }
 
+    /// Set the content of a maintainer script.
+    ///
+    /// The entry is always marked as executable, as `dpkg` requires.
+    pub fn set_maintainer_script(
+        mut self,
+        script: MaintainerScript,
+        entry: impl Into<FileEntry>,
+    ) -> Result<Self> {
+        let mut entry = entry.into();
+        entry.set_executable(true);
+
+        self.extra_files.add_file_entry(script.filename(), entry)?;
+
+        Ok(self)
+    }
+
+    /// Register an installed file as a configuration file.
+    ///
+    /// `path` is the relative path the file will be installed to. e.g. `etc/myapp.conf`.
+    /// It will be written to the `conffiles` file as an absolute path. A leading `/` or
+    /// `./` is ignored. Paths containing `..` are rejected.
+    pub fn add_conffile(mut self, path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+
+        if path.components().any(|c| c == Component::ParentDir) {
+            return Err(FileManifestError::IllegalRelativePath(path.display().to_string()).into());
+        }
+
+        self.conffiles.insert(
+            path.components()
+                .filter(|c| !matches!(c, Component::RootDir | Component::CurDir))
+                .collect(),
+        );
+
+        Ok(self)
+    }
+
     /// Add a data file to be indexed.
     ///
     /// This should be called for every file in the corresponding `data.tar`
@@ -289,7 +469,7 @@ impl<'a> ControlTarBuilder<'a> {
         let digest = context.finalize();
 
         let mut entry = Vec::new();
-        entry.write_all(&digest.to_ascii_lowercase())?;
+        entry.write_all(hex::encode(digest).as_bytes())?;
         entry.write_all(b"  ")?;
         entry.write_all(path.as_ref().to_raw_bytes().as_ref())?;
         entry.write_all(b"\n")?;
@@ -323,12 +503,36 @@ impl<'a> ControlTarBuilder<'a> {
         manifest.add_file_entry("control", control_data)?;
         manifest.add_file_entry("md5sums", self.md5sums.concat::<u8>())?;
 
+        if !self.conffiles.is_empty() {
+            let mut conffiles = Vec::new();
+            for path in &self.conffiles {
+                conffiles.write_all(b"/")?;
+                conffiles.write_all(path.as_os_str().to_raw_bytes().as_ref())?;
+                conffiles.write_all(b"\n")?;
+            }
+
+            manifest.add_file_entry("conffiles", conffiles)?;
+        }
+
         write_deb_tar(writer, &manifest, self.mtime())
     }
 }
 
 /// Write a tar archive suitable for inclusion in a `.deb` archive.
 pub fn write_deb_tar<W: Write>(writer: W, files: &FileManifest, mtime: u64) -> Result<()> {
+    write_deb_tar_with_modes(writer, files, mtime, &BTreeMap::new())
+}
+
+/// Write a tar archive suitable for inclusion in a `.deb` archive with explicit permissions.
+///
+/// `modes` maps relative file and directory paths to their mode. Paths not in `modes`
+/// get the same defaults as [write_deb_tar()].
+pub fn write_deb_tar_with_modes<W: Write>(
+    writer: W,
+    files: &FileManifest,
+    mtime: u64,
+    modes: &BTreeMap<PathBuf, u32>,
+) -> Result<()> {
     let mut builder = tar::Builder::new(writer);
 
     // Add root directory entry.
@@ -343,7 +547,7 @@ pub fn write_deb_tar<W: Write>(writer: W, files: &FileManifest, mtime: u64) -> R
     for directory in files.relative_directories() {
         let mut header = new_tar_header(mtime)?;
         set_header_path(&mut builder, &mut header, &directory, true)?;
-        header.set_mode(0o755);
+        header.set_mode(modes.get(&directory).copied().unwrap_or(0o755));
         header.set_size(0);
         header.set_cksum();
         builder.append(&header, &*vec![])?;
@@ -351,15 +555,33 @@ pub fn write_deb_tar<W: Write>(writer: W, files: &FileManifest, mtime: u64) -> R
 
     // FileManifest is backed by a BTreeMap, so iteration is deterministic.
     for (rel_path, content) in files.iter_entries() {
+        if let Some(target) = content.link_target() {
+            let mut header = new_tar_header(mtime)?;
+            set_header_path(&mut builder, &mut header, rel_path, false)?;
+            set_header_link_name(&mut builder, &mut header, target)?;
+            header.set_entry_type(tar::EntryType::Symlink);
+            header.set_mode(0o777);
+            header.set_size(0);
+            header.set_cksum();
+            builder.append(&header, &*vec![])?;
+
+            continue;
+        }
+
         let data = content.resolve_content()?;
 
         let mut header = new_tar_header(mtime)?;
         set_header_path(&mut builder, &mut header, rel_path, false)?;
-        header.set_mode(if content.is_executable() {
-            0o755
-        } else {
-            0o644
-        });
+        header.set_mode(
+            modes
+                .get(rel_path)
+                .copied()
+                .unwrap_or(if content.is_executable() {
+                    0o755
+                } else {
+                    0o644
+                }),
+        );
         header.set_size(data.len() as _);
         header.set_cksum();
         builder.append(&header, &*data)?;
@@ -372,7 +594,7 @@ pub fn write_deb_tar<W: Write>(writer: W, files: &FileManifest, mtime: u64) -> R
 
 #[cfg(test)]
 mod tests {
-    use {super::*, crate::control::ControlParagraph, std::path::PathBuf};
+    use {super::*, crate::control::ControlParagraph};
 
     #[test]
     fn test_write_control_tar_simple() -> Result<()> {
@@ -410,6 +632,77 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_write_control_tar_conffiles_and_scripts() -> Result<()> {
+        let mut control_para = ControlParagraph::default();
+        control_para.set_field_from_string("Package".into(), "mypackage".into());
+
+        let mut control = ControlFile::default();
+        control.add_paragraph(control_para);
+
+        let builder = ControlTarBuilder::new(control)
+            .set_mtime(Some(SystemTime::UNIX_EPOCH))
+            .set_maintainer_script(MaintainerScript::Postinst, b"#!/bin/sh\n".to_vec())?
+            .add_data_file("usr/bin/myapp", &mut std::io::Cursor::new("data"))?
+            .add_data_file("etc/myapp.conf", &mut std::io::Cursor::new(""))?
+            .add_data_file("etc/other.conf", &mut std::io::Cursor::new(""))?
+            .add_data_file("etc/third.conf", &mut std::io::Cursor::new(""))?
+            .add_conffile("etc/myapp.conf")?
+            .add_conffile("/etc/other.conf")?
+            .add_conffile("./etc/third.conf")?
+            .add_conffile("/etc/myapp.conf")?;
+
+        assert!(matches!(
+            ControlTarBuilder::new(ControlFile::default()).add_conffile("etc/../myapp.conf"),
+            Err(DebianError::FileManifestError(
+                FileManifestError::IllegalRelativePath(_)
+            ))
+        ));
+
+        let mut buffer = vec![];
+        builder.write(&mut buffer)?;
+
+        let mut archive = tar::Archive::new(std::io::Cursor::new(buffer));
+
+        let mut seen = BTreeSet::new();
+        for entry in archive.entries()? {
+            let mut entry = entry?;
+            let path = entry.path()?.display().to_string();
+            let mode = entry.header().mode()?;
+            let mut data = String::new();
+            entry.read_to_string(&mut data)?;
+
+            match path.as_str() {
+                "./" | "./control" => {}
+                "./conffiles" => {
+                    assert_eq!(mode, 0o644);
+                    assert_eq!(data, "/etc/myapp.conf\n/etc/other.conf\n/etc/third.conf\n");
+                }
+                "./md5sums" => {
+                    assert_eq!(mode, 0o644);
+                    assert_eq!(
+                        data,
+                        "8d777f385d3dfec8815d20f7496026dc  usr/bin/myapp\n\
+                         d41d8cd98f00b204e9800998ecf8427e  etc/myapp.conf\n\
+                         d41d8cd98f00b204e9800998ecf8427e  etc/other.conf\n\
+                         d41d8cd98f00b204e9800998ecf8427e  etc/third.conf\n"
+                    );
+                }
+                "./postinst" => {
+                    assert_eq!(mode, 0o755);
+                    assert_eq!(data, "#!/bin/sh\n");
+                }
+                _ => panic!("unexpected archive entry: {}", path),
+            }
+
+            seen.insert(path);
+        }
+
+        assert_eq!(seen.len(), 5);
+
+        Ok(())
+    }
+
     #[test]
     fn test_write_data_tar_one_file() -> Result<()> {
         let mut manifest = FileManifest::default();
@@ -465,6 +758,164 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_write_data_tar_symlink() -> Result<()> {
+        let long_target = PathBuf::from(format!("../lib/{}/myapp", "u".repeat(200)));
+
+        let mut manifest = FileManifest::new_with_links();
+        manifest.add_file_entry(
+            "usr/lib/myapp/myapp",
+            FileEntry::new_from_data(vec![42], true),
+        )?;
+        manifest.add_symlink("usr/bin/myapp", "../lib/myapp/myapp")?;
+        manifest.add_symlink("usr/bin/myapp-long", &long_target)?;
+
+        let mut buffer = vec![];
+        write_deb_tar(&mut buffer, &manifest, 2)?;
+
+        let mut archive = tar::Archive::new(std::io::Cursor::new(buffer));
+
+        let mut links = vec![];
+        for entry in archive.entries()? {
+            let entry = entry?;
+
+            if entry.header().entry_type() == tar::EntryType::Symlink {
+                assert_eq!(entry.header().mode()?, 0o777);
+                assert_eq!(entry.size(), 0);
+                links.push((
+                    entry.path()?.to_path_buf(),
+                    entry.link_name()?.unwrap().to_path_buf(),
+                ));
+            }
+        }
+
+        assert_eq!(
+            links,
+            vec![
+                (
+                    PathBuf::from("./usr/bin/myapp"),
+                    PathBuf::from("../lib/myapp/myapp")
+                ),
+                (PathBuf::from("./usr/bin/myapp-long"), long_target),
+            ]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_write_data_tar_modes() -> Result<()> {
+        let mut manifest = FileManifest::default();
+        manifest.add_file_entry("usr/bin/myapp", FileEntry::new_from_data(vec![42], true))?;
+        manifest.add_file_entry("usr/bin/other", FileEntry::new_from_data(vec![42], false))?;
+        manifest.add_file_entry(
+            "etc/myapp/secret.conf",
+            FileEntry::new_from_data(vec![42], false),
+        )?;
+
+        let modes = BTreeMap::from_iter([
+            (PathBuf::from("usr/bin/myapp"), 0o4755),
+            (PathBuf::from("etc/myapp/secret.conf"), 0o640),
+            (PathBuf::from("etc/myapp"), 0o750),
+        ]);
+
+        let mut buffer = vec![];
+        write_deb_tar_with_modes(&mut buffer, &manifest, 2, &modes)?;
+
+        let mut archive = tar::Archive::new(std::io::Cursor::new(buffer));
+
+        let mut seen = BTreeMap::new();
+        for entry in archive.entries()? {
+            let entry = entry?;
+            seen.insert(entry.path()?.display().to_string(), entry.header().mode()?);
+        }
+
+        assert_eq!(
+            seen,
+            BTreeMap::from_iter([
+                ("./".to_string(), 0o755),
+                ("./etc/".to_string(), 0o755),
+                ("./etc/myapp/".to_string(), 0o750),
+                ("./etc/myapp/secret.conf".to_string(), 0o640),
+                ("./usr/".to_string(), 0o755),
+                ("./usr/bin/".to_string(), 0o755),
+                ("./usr/bin/myapp".to_string(), 0o4755),
+                ("./usr/bin/other".to_string(), 0o644),
+            ])
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_set_file_mode() -> Result<()> {
+        let builder = DebBuilder::new(ControlFile::default())
+            .install_file("usr/bin/myapp", FileEntry::new_from_data(vec![42], true))?
+            .set_file_mode("usr/bin/myapp", 0o4750)?;
+        assert_eq!(
+            builder.file_modes.get(Path::new("usr/bin/myapp")),
+            Some(&0o4750)
+        );
+
+        assert!(matches!(
+            builder.set_file_mode("usr/bin/myapp", 0o100755),
+            Err(DebianError::DebInvalidFileMode(0o100755))
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_install_symlink_over_file() -> Result<()> {
+        let builder = DebBuilder::new(ControlFile::default())
+            .install_file("usr/bin/myapp", FileEntry::new_from_data(vec![42], true))?;
+
+        assert!(matches!(
+            builder.install_symlink("usr/bin/myapp", "../lib/myapp/myapp"),
+            Err(DebianError::DebSymlinkPathConflict(path)) if path == "usr/bin/myapp"
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_install_file_link_over_file() -> Result<()> {
+        let builder = DebBuilder::new(ControlFile::default())
+            .install_file("usr/bin/myapp", FileEntry::new_from_data(vec![42], true))?;
+
+        let mut link = FileEntry::new_from_data(vec![], false);
+        link.set_link_target(PathBuf::from("../lib/myapp/myapp"));
+
+        assert!(matches!(
+            builder.install_file("usr/bin/myapp", link.clone()),
+            Err(DebianError::DebSymlinkPathConflict(path)) if path == "usr/bin/myapp"
+        ));
+
+        // Links at new paths are accepted and don't get checksums.
+        let builder = DebBuilder::new(ControlFile::default())
+            .install_file("usr/bin/myapp", link)?
+            .install_file(
+                "usr/lib/myapp/myapp",
+                FileEntry::new_from_data(vec![42], true),
+            )?;
+        assert_eq!(builder.control_builder.md5sums.len(), 1);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_install_conffile_symlink() -> Result<()> {
+        let mut entry = FileEntry::new_from_data(vec![], false);
+        entry.set_link_target(PathBuf::from("../usr/share/myapp/myapp.conf"));
+
+        assert!(matches!(
+            DebBuilder::new(ControlFile::default()).install_conffile("etc/myapp.conf", entry),
+            Err(DebianError::DebConffileNotRegularFile(path)) if path == "etc/myapp.conf"
+        ));
+
+        Ok(())
+    }
+
     #[test]
     fn test_write_deb() -> Result<()> {
         let mut control_para = ControlParagraph::default();
@@ -12,6 +12,38 @@ use {crate::error::Result, std::io::Read};
 pub mod builder;
 pub mod reader;
 
+/// A maintainer script in the `control.tar` archive of a `.deb` file.
+///
+/// Maintainer scripts are executed by the package manager during package installation,
+/// upgrade, and removal. See
+/// <https://www.debian.org/doc/debian-policy/ch-maintainerscripts.html>.
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum MaintainerScript {
+    /// Executed before the package is unpacked.
+    Preinst,
+    /// Executed after the package is unpacked.
+    Postinst,
+    /// Executed before the package is removed.
+    Prerm,
+    /// Executed after the package is removed.
+    Postrm,
+    /// The debconf configuration script.
+    Config,
+}
+
+impl MaintainerScript {
+    /// The filename of this script in the `control.tar` archive.
+    pub fn filename(&self) -> &'static str {
+        match self {
+            Self::Preinst => "preinst",
+            Self::Postinst => "postinst",
+            Self::Prerm => "prerm",
+            Self::Postrm => "postrm",
+            Self::Config => "config",
+        }
+    }
+}
+
 /// Compression format to apply to `.deb` files.
 pub enum DebCompression {
     /// Do not compress contents of `.deb` files.
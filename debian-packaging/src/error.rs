@@ -65,6 +65,15 @@ pub enum DebianError {
     #[error("unknown compression in deb archive file: {0}")]
     DebUnknownCompression(String),
 
+    #[error("cannot install symlink over already registered path: {0}")]
+    DebSymlinkPathConflict(String),
+
+    #[error("invalid file mode {0:#o}; only permission bits are allowed")]
+    DebInvalidFileMode(u32),
+
+    #[error("conffile is not a regular file: {0}")]
+    DebConffileNotRegularFile(String),
+
     #[error("do not know how to construct repository reader from URL: {0}")]
     RepositoryReaderUnrecognizedUrl(String),
 
@@ -104,6 +113,20 @@ pub enum DebianError {
     #[error(".deb not available: {0}")]
     RepositoryBuildDebNotAvailable(&'static str),
 
+    #[error("changelog must have an entry to derive the source package version")]
+    SourcePackageNoChangelogEntry,
+
+    #[error("source package tarballs must be gzip or xz compressed")]
+    SourcePackageUnsupportedCompression,
+
+    #[error("debian/control must define at least 1 binary package")]
+    SourcePackageNoBinaryPackages,
+
+    #[error(
+        "changelog entry is for source package {changelog} but debian/control defines {control}"
+    )]
+    SourcePackageChangelogSourceMismatch { changelog: String, control: String },
+
     #[error("expected 1 paragraph in control file; got {0}")]
     ReleaseControlParagraphMismatch(usize),
 
@@ -47,6 +47,9 @@ A `.deb` file defines a Debian package. Readers and writers of `.deb` files exis
 [deb] module. To read the contents of a `.deb` defining a binary package, use
 [deb::reader::BinaryPackageReader]. To create new `.deb` files, use [deb::builder::DebBuilder].
 
+To create Debian source packages (`.dsc` files and their tarballs), use
+[source_package_builder::SourcePackageBuilder].
+
 A common primitive within Debian packaging is *control files*. These consist of *paragraphs*
 of key-value metadata. Low-level control file primitives are defined in the [control] module.
 [control::ControlParagraph] defines a paragraph, which consists of [control::ControlField].
@@ -125,4 +128,5 @@ pub mod io;
 pub mod package_version;
 pub mod repository;
 pub mod signing_key;
+pub mod source_package_builder;
 pub mod source_package_control;
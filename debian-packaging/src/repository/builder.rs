@@ -23,6 +23,7 @@ use {
             release::{ChecksumType, ReleaseFile, DATE_FORMAT},
             Compression, PublishEvent, RepositoryPathVerificationState, RepositoryWriter,
         },
+        signing_key::detached_sign,
     },
     chrono::{DateTime, Utc},
     futures::{AsyncRead, AsyncReadExt, StreamExt, TryStreamExt},
@@ -698,7 +699,7 @@ impl<'cf> RepositoryBuilder<'cf> {
     pub async fn publish_pool_artifacts<F>(
         &self,
         resolver: &impl DataResolver,
-        writer: &impl RepositoryWriter,
+        writer: &(impl RepositoryWriter + ?Sized),
         threads: usize,
         progress_cb: &Option<F>,
     ) -> Result<()>
@@ -898,7 +899,6 @@ impl<'cf> RepositoryBuilder<'cf> {
                 .get(checksum.field_name())
                 .unwrap_or(&default);
 
-            let longest_path = entries.keys().map(|x| x.len()).max().unwrap_or_default();
             let longest_size = entries
                 .values()
                 .map(|(size, _)| format!("{}", size).len())
@@ -910,11 +910,10 @@ impl<'cf> RepositoryBuilder<'cf> {
                 std::iter::once("".to_string())
                     .chain(entries.iter().map(|(path, (size, digest))| {
                         format!(
-                            " {:<path_width$} {:>size_width$} {}",
-                            path,
-                            size,
+                            " {} {:>size_width$} {}",
                             digest,
-                            path_width = longest_path,
+                            size,
+                            path,
                             size_width = longest_size
                         )
                     }))
@@ -937,7 +936,7 @@ impl<'cf> RepositoryBuilder<'cf> {
     /// that does not exist.
     pub async fn publish_indices<F, PW>(
         &self,
-        writer: &impl RepositoryWriter,
+        writer: &(impl RepositoryWriter + ?Sized),
         path_prefix: Option<&str>,
         threads: usize,
         progress_cb: &Option<F>,
@@ -1000,13 +999,18 @@ impl<'cf> RepositoryBuilder<'cf> {
 
         let release = self.create_release_file(index_paths.into_iter())?;
 
-        let (release_path, inrelease_path) = if let Some(prefix) = path_prefix {
+        let (release_path, inrelease_path, release_gpg_path) = if let Some(prefix) = path_prefix {
             (
                 format!("{}/Release", prefix.trim_matches('/')),
                 format!("{}/InRelease", prefix.trim_matches('/')),
+                format!("{}/Release.gpg", prefix.trim_matches('/')),
             )
         } else {
-            ("Release".to_string(), "InRelease".to_string())
+            (
+                "Release".to_string(),
+                "InRelease".to_string(),
+                "Release.gpg".to_string(),
+            )
         };
 
         if let Some(cb) = progress_cb {
@@ -1028,29 +1032,46 @@ impl<'cf> RepositoryBuilder<'cf> {
         }
 
         if let Some((key, password)) = signing_key {
+            // We produce 2 signatures and the password function can only be called once.
+            let password = password();
+
             let inrelease_content = cleartext_sign(
                 key,
-                password,
+                || password.clone(),
                 HashAlgorithm::SHA2_256,
                 std::io::Cursor::new(release.to_string().as_bytes()),
             )?;
 
-            if let Some(cb) = progress_cb {
-                cb(PublishEvent::IndexFileToWrite(inrelease_path.clone()));
-            }
+            // Older apt clients and some tools only look for a detached signature in
+            // `Release.gpg`.
+            let release_gpg_content = detached_sign(
+                key,
+                || password.clone(),
+                HashAlgorithm::SHA2_256,
+                std::io::Cursor::new(release.to_string().as_bytes()),
+            )?;
 
-            let inrelease_write = writer
-                .write_path(
-                    inrelease_path.into(),
-                    Box::pin(futures::io::Cursor::new(inrelease_content.into_bytes())),
-                )
-                .await?;
+            for (path, content) in [
+                (inrelease_path, inrelease_content),
+                (release_gpg_path, release_gpg_content),
+            ] {
+                if let Some(cb) = progress_cb {
+                    cb(PublishEvent::IndexFileToWrite(path.clone()));
+                }
 
-            if let Some(cb) = progress_cb {
-                cb(PublishEvent::IndexFileWritten(
-                    inrelease_write.path.to_string(),
-                    inrelease_write.bytes_written,
-                ));
+                let write = writer
+                    .write_path(
+                        path.into(),
+                        Box::pin(futures::io::Cursor::new(content.into_bytes())),
+                    )
+                    .await?;
+
+                if let Some(cb) = progress_cb {
+                    cb(PublishEvent::IndexFileWritten(
+                        write.path.to_string(),
+                        write.bytes_written,
+                    ));
+                }
             }
         }
 
@@ -1065,7 +1086,8 @@ impl<'cf> RepositoryBuilder<'cf> {
     ///
     /// 1. Publish missing pool artifacts.
     /// 2. Publish *indices* files (e.g. `Packages` lists).
-    /// 3. Publish the `InRelease` and `Release` file.
+    /// 3. Publish the `Release` file and, if a signing key is given, the `InRelease` and
+    ///    `Release.gpg` files.
     ///
     /// `writer` is a [RepositoryWriter] used to perform I/O for writing output files.
     /// `resolver` is a [DataResolver] for resolving pool paths. It will be consulted
@@ -1085,7 +1107,7 @@ impl<'cf> RepositoryBuilder<'cf> {
     /// some typing.
     pub async fn publish<F, PW>(
         &self,
-        writer: &impl RepositoryWriter,
+        writer: &(impl RepositoryWriter + ?Sized),
         resolver: &impl DataResolver,
         distribution_path: &str,
         threads: usize,
@@ -1114,7 +1136,7 @@ impl<'cf> RepositoryBuilder<'cf> {
 
 async fn get_path_and_copy<'a, 'b>(
     resolver: &impl DataResolver,
-    writer: &impl RepositoryWriter,
+    writer: &(impl RepositoryWriter + ?Sized),
     artifact: &'a BinaryPackagePoolArtifact<'b>,
 ) -> Result<&'a BinaryPackagePoolArtifact<'b>> {
     // It would be slightly more defensive to plug in the content validator
@@ -1324,9 +1346,16 @@ mod tests {
     use {
         super::*,
         crate::{
-            repository::{filesystem::FilesystemRepositoryWriter, reader_from_str},
+            control::ControlFile,
+            deb::builder::DebBuilder,
+            io::PathMappingDataResolver,
+            repository::{
+                filesystem::{FilesystemRepositoryReader, FilesystemRepositoryWriter},
+                reader_from_str,
+            },
             signing_key::{create_self_signed_key, signing_secret_key_params_builder},
         },
+        pgp::{Deserializable, StandaloneSignature},
         tempfile::TempDir,
     };
 
@@ -1376,4 +1405,80 @@ mod tests {
 
         Ok(())
     }
+
+    #[tokio::test]
+    async fn publish_local_debs() -> Result<()> {
+        let td = temp_dir()?;
+        let source_dir = td.path().join("debs");
+        let repo_dir = td.path().join("repo");
+        std::fs::create_dir_all(&source_dir)?;
+
+        let mut control = ControlParagraph::default();
+        for (k, v) in [
+            ("Package", "myapp"),
+            ("Version", "1.0-1"),
+            ("Architecture", "amd64"),
+            ("Maintainer", "Me <me@example.com>"),
+            ("Description", "my app\n more details"),
+        ] {
+            control.set_field_from_string(k.into(), v.into());
+        }
+        let mut control_file = ControlFile::default();
+        control_file.add_paragraph(control);
+
+        let mut deb = vec![];
+        DebBuilder::new(control_file).write(&mut deb)?;
+        std::fs::write(source_dir.join("myapp_1.0-1_amd64.deb"), &deb)?;
+
+        let mut builder = RepositoryBuilder::new_recommended(
+            ["amd64"].into_iter(),
+            ["main"].into_iter(),
+            "stable",
+            "mydist",
+        );
+
+        let mut resolver =
+            PathMappingDataResolver::new(FilesystemRepositoryReader::new(&source_dir));
+        let pool_path = builder.add_binary_deb(
+            "main",
+            &InMemoryDebFile::new("myapp_1.0-1_amd64.deb".into(), deb),
+        )?;
+        assert_eq!(pool_path, "pool/main/m/myapp/myapp_1.0-1_amd64.deb");
+        resolver.add_path_map(&pool_path, "myapp_1.0-1_amd64.deb");
+
+        let key_params = signing_secret_key_params_builder("someone@example.com")
+            .build()
+            .unwrap();
+        let (private, public) = create_self_signed_key(key_params, String::new)?;
+
+        builder
+            .publish(
+                &FilesystemRepositoryWriter::new(&repo_dir),
+                &resolver,
+                "dists/mydist",
+                2,
+                &NO_PROGRESS_CB,
+                Some((&private, String::new)),
+            )
+            .await?;
+
+        assert!(repo_dir.join(&pool_path).exists());
+
+        let release = std::fs::read(repo_dir.join("dists/mydist/Release"))?;
+        let release_gpg = std::fs::read_to_string(repo_dir.join("dists/mydist/Release.gpg"))?;
+        let (signature, _) = StandaloneSignature::from_string(&release_gpg)?;
+        signature.verify(&public, &release)?;
+
+        let reader = reader_from_str(format!("file://{}", repo_dir.display()))?;
+        let release_reader = reader.release_reader("mydist").await?;
+        let packages = release_reader
+            .resolve_packages("main", "amd64", false)
+            .await?;
+        assert_eq!(packages.len(), 1);
+        let package = packages.iter().next().unwrap();
+        assert_eq!(package.package()?, "myapp");
+        assert_eq!(package.field_str("Filename"), Some(pool_path.as_str()));
+
+        Ok(())
+    }
 }
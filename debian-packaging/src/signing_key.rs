@@ -5,14 +5,16 @@
 /*! PGP signing keys. */
 
 use {
+    chrono::SubsecRound,
     pgp::{
         crypto::{HashAlgorithm, SymmetricKeyAlgorithm},
-        types::{CompressionAlgorithm, SecretKeyTrait},
+        packet::{SignatureConfig, SignatureType, Subpacket},
+        types::{CompressionAlgorithm, KeyVersion, SecretKeyTrait},
         Deserializable, KeyType, SecretKeyParams, SecretKeyParamsBuilder, SignedPublicKey,
-        SignedSecretKey,
+        SignedSecretKey, StandaloneSignature,
     },
-    smallvec::smallvec,
-    std::io::Cursor,
+    smallvec::{smallvec, SmallVec},
+    std::io::{Cursor, Read},
     strum::EnumIter,
 };
 
@@ -187,6 +189,40 @@ where
     Ok((secret_key_signed, public_key_signed))
 }
 
+/// Produce a detached, ASCII armored signature over data.
+///
+/// This is the format of `Release.gpg` files in Debian repositories. Unlike
+/// [pgp_cleartext::cleartext_sign()], the signed data is not included in the output.
+pub fn detached_sign<PW, R>(
+    key: &impl SecretKeyTrait,
+    key_pw: PW,
+    hash_algorithm: HashAlgorithm,
+    data: R,
+) -> pgp::errors::Result<String>
+where
+    PW: FnOnce() -> String,
+    R: Read,
+{
+    let hashed_subpackets = vec![
+        Subpacket::IssuerFingerprint(KeyVersion::V4, SmallVec::from_slice(&key.fingerprint())),
+        Subpacket::SignatureCreationTime(chrono::Utc::now().trunc_subsecs(0)),
+    ];
+    let unhashed_subpackets = vec![Subpacket::Issuer(key.key_id())];
+
+    let config = SignatureConfig::new_v4(
+        Default::default(),
+        SignatureType::Binary,
+        key.algorithm(),
+        hash_algorithm,
+        hashed_subpackets,
+        unhashed_subpackets,
+    );
+
+    let signature = config.sign(key, key_pw, data)?;
+
+    StandaloneSignature::new(signature).to_armored_string(None)
+}
+
 #[cfg(test)]
 mod test {
     use {super::*, strum::IntoEnumIterator};
@@ -213,4 +249,24 @@ mod test {
 
         Ok(())
     }
+
+    #[test]
+    fn detached_signature() -> pgp::errors::Result<()> {
+        let params = signing_secret_key_params_builder("Me <someone@example.com>").build()?;
+        let (private, public) = create_self_signed_key(params, String::new)?;
+
+        let armored = detached_sign(
+            &private,
+            String::new,
+            HashAlgorithm::SHA2_256,
+            Cursor::new(b"hello, world"),
+        )?;
+        assert!(armored.starts_with("-----BEGIN PGP SIGNATURE-----"));
+
+        let (signature, _) = StandaloneSignature::from_string(&armored)?;
+        signature.verify(&public, b"hello, world")?;
+        assert!(signature.verify(&public, b"goodbye").is_err());
+
+        Ok(())
+    }
 }
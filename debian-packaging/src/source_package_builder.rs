@@ -0,0 +1,721 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+/*! Create Debian source packages.
+
+A Debian source package consists of a `.dsc` file (see
+[crate::debian_source_control::DebianSourceControlFile]) and 1 or more tarballs holding
+the source code and the `debian/` directory defining how to build it.
+
+This module produces source packages in the `3.0 (quilt)` and `3.0 (native)` formats.
+See <https://manpages.debian.org/unstable/dpkg-dev/dpkg-source.1.en.html> for the
+specification of these formats.
+*/
+
+use {
+    crate::{
+        changelog::Changelog,
+        control::{ControlField, ControlParagraph},
+        deb::{builder::write_deb_tar, DebCompression},
+        debian_source_control::DebianSourceControlFile,
+        error::{DebianError, Result},
+        io::{MultiContentDigest, MultiDigester},
+        package_version::PackageVersion,
+        repository::release::ChecksumType,
+        source_package_control::SourcePackageControlFile,
+    },
+    pgp::{crypto::HashAlgorithm, types::SecretKeyTrait},
+    pgp_cleartext::cleartext_sign,
+    simple_file_manifest::{FileEntry, FileManifest},
+    std::{
+        path::{Path, PathBuf},
+        time::SystemTime,
+    },
+};
+
+/// Default content of the `debian/rules` file.
+///
+/// This defers all build steps to `debhelper`.
+pub const DEFAULT_RULES: &str = "#!/usr/bin/make -f\n\n%:\n\tdh $@\n";
+
+/// Fields from the general paragraph of `debian/control` that are copied to the `.dsc` file.
+const DSC_COPIED_FIELDS: &[&str] = &[
+    "Uploaders",
+    "Homepage",
+    "Standards-Version",
+    "Vcs-Arch",
+    "Vcs-Bzr",
+    "Vcs-Cvs",
+    "Vcs-Darcs",
+    "Vcs-Git",
+    "Vcs-Hg",
+    "Vcs-Mtn",
+    "Vcs-Svn",
+    "Vcs-Browser",
+    "Testsuite",
+    "Build-Depends",
+    "Build-Depends-Arch",
+    "Build-Depends-Indep",
+    "Build-Conflicts",
+    "Build-Conflicts-Arch",
+    "Build-Conflicts-Indep",
+];
+
+/// A file constituting a source package, other than the `.dsc` file.
+#[derive(Clone, Debug)]
+pub struct SourcePackageFile {
+    /// The filename, without any directory components.
+    pub filename: String,
+    /// The content of the file.
+    pub data: Vec<u8>,
+    /// Content digests of the file.
+    pub digests: MultiContentDigest,
+}
+
+impl SourcePackageFile {
+    fn new(filename: String, data: Vec<u8>) -> Self {
+        let mut digester = MultiDigester::default();
+        digester.update(&data);
+
+        Self {
+            filename,
+            data,
+            digests: digester.finish(),
+        }
+    }
+}
+
+/// A Debian source package produced by [SourcePackageBuilder].
+pub struct SourcePackage {
+    dsc: DebianSourceControlFile<'static>,
+    dsc_filename: String,
+    files: Vec<SourcePackageFile>,
+}
+
+impl SourcePackage {
+    /// The `.dsc` file describing this source package.
+    pub fn dsc(&self) -> &DebianSourceControlFile<'static> {
+        &self.dsc
+    }
+
+    /// The filename of the `.dsc` file.
+    pub fn dsc_filename(&self) -> &str {
+        &self.dsc_filename
+    }
+
+    /// Files referenced by the `.dsc` file.
+    pub fn iter_files(&self) -> impl Iterator<Item = &SourcePackageFile> {
+        self.files.iter()
+    }
+
+    /// Obtain the serialized `.dsc` file, optionally PGP signing it.
+    ///
+    /// If a signing key is provided, the content is a PGP cleartext signature, like
+    /// `.dsc` files uploaded to Debian.
+    pub fn dsc_data<PW>(&self, signing_key: Option<(&impl SecretKeyTrait, PW)>) -> Result<Vec<u8>>
+    where
+        PW: FnOnce() -> String,
+    {
+        let content = self.dsc.to_string();
+
+        if let Some((key, password)) = signing_key {
+            Ok(cleartext_sign(
+                key,
+                password,
+                HashAlgorithm::SHA2_256,
+                std::io::Cursor::new(content.as_bytes()),
+            )?
+            .into_bytes())
+        } else {
+            Ok(content.into_bytes())
+        }
+    }
+
+    /// Write the `.dsc` and referenced files to a directory.
+    ///
+    /// Returns the paths of written files. The `.dsc` file is last.
+    pub fn write_to_directory<PW>(
+        &self,
+        dest_dir: impl AsRef<Path>,
+        signing_key: Option<(&impl SecretKeyTrait, PW)>,
+    ) -> Result<Vec<PathBuf>>
+    where
+        PW: FnOnce() -> String,
+    {
+        let dest_dir = dest_dir.as_ref();
+        std::fs::create_dir_all(dest_dir)?;
+
+        let mut paths = vec![];
+
+        for file in &self.files {
+            let path = dest_dir.join(&file.filename);
+            std::fs::write(&path, &file.data)?;
+            paths.push(path);
+        }
+
+        let path = dest_dir.join(&self.dsc_filename);
+        std::fs::write(&path, self.dsc_data(signing_key)?)?;
+        paths.push(path);
+
+        Ok(paths)
+    }
+}
+
+/// A builder for Debian source packages.
+///
+/// Instances are constructed from the content of the `debian/control` file and the
+/// `debian/changelog` file. The source package name comes from the `Source` field of
+/// the control file and the version comes from the most recent changelog entry.
+///
+/// If the version has a Debian revision (e.g. `1.0-1`), a `3.0 (quilt)` package is
+/// produced, consisting of an `.orig.tar` holding upstream files and a `.debian.tar`
+/// holding the `debian/` directory. Otherwise a `3.0 (native)` package with a single
+/// tarball is produced.
+///
+/// The `debian/control`, `debian/changelog`, and `debian/source/format` files are
+/// always derived from builder state. A `debian/rules` file using [DEFAULT_RULES] is
+/// added unless one is registered via [Self::add_debian_file()].
+pub struct SourcePackageBuilder<'a> {
+    control: SourcePackageControlFile<'a>,
+    changelog: Changelog<'a>,
+    upstream_files: FileManifest,
+    debian_files: FileManifest,
+    compression: DebCompression,
+    mtime: Option<SystemTime>,
+}
+
+impl<'a> SourcePackageBuilder<'a> {
+    /// Construct a new instance from `debian/control` and `debian/changelog` content.
+    pub fn new(control: SourcePackageControlFile<'a>, changelog: Changelog<'a>) -> Self {
+        Self {
+            control,
+            changelog,
+            upstream_files: FileManifest::default(),
+            debian_files: FileManifest::default(),
+            compression: DebCompression::Xz(6),
+            mtime: None,
+        }
+    }
+
+    /// Set the compression format of tarballs.
+    ///
+    /// `dpkg-source` only supports gzip and xz from the available formats. Attempting
+    /// to build with other formats will error.
+    #[must_use]
+    pub fn set_compression(mut self, compression: DebCompression) -> Self {
+        self.compression = compression;
+        self
+    }
+
+    /// Set the modified time to use on tarball members.
+    ///
+    /// If not called, the current time will be used.
+    #[must_use]
+    pub fn set_mtime(mut self, time: Option<SystemTime>) -> Self {
+        self.mtime = time;
+        self
+    }
+
+    /// Register an upstream source file.
+    ///
+    /// Paths are relative to the root of the source tree. e.g. `src/main.rs`.
+    pub fn add_upstream_file(
+        mut self,
+        path: impl AsRef<Path>,
+        entry: impl Into<FileEntry>,
+    ) -> Result<Self> {
+        self.upstream_files.add_file_entry(path, entry)?;
+        Ok(self)
+    }
+
+    /// Register a file in the `debian/` directory.
+    ///
+    /// Paths are relative to the `debian/` directory. e.g. `copyright` or `rules`.
+    pub fn add_debian_file(
+        mut self,
+        path: impl AsRef<Path>,
+        entry: impl Into<FileEntry>,
+    ) -> Result<Self> {
+        self.debian_files.add_file_entry(path, entry)?;
+        Ok(self)
+    }
+
+    fn mtime(&self) -> u64 {
+        self.mtime
+            .unwrap_or_else(std::time::SystemTime::now)
+            .duration_since(std::time::UNIX_EPOCH)
+            .expect("times before UNIX epoch not accepted")
+            .as_secs()
+    }
+
+    /// Resolve the files constituting the `debian/` directory.
+    fn debian_directory(&self, native: bool) -> Result<FileManifest> {
+        let mut manifest = FileManifest::default();
+
+        if !self.debian_files.has_path("rules") {
+            manifest.add_file_entry(
+                "debian/rules",
+                FileEntry::new_from_data(DEFAULT_RULES.as_bytes(), true),
+            )?;
+        }
+
+        for (path, entry) in self.debian_files.iter_entries() {
+            manifest.add_file_entry(Path::new("debian").join(path), entry.clone())?;
+        }
+
+        let mut control = vec![];
+        self.control.write(&mut control)?;
+        manifest.add_file_entry("debian/control", control)?;
+
+        let mut changelog = vec![];
+        self.changelog.write(&mut changelog)?;
+        manifest.add_file_entry("debian/changelog", changelog)?;
+
+        manifest.add_file_entry(
+            "debian/source/format",
+            if native {
+                &b"3.0 (native)\n"[..]
+            } else {
+                &b"3.0 (quilt)\n"[..]
+            },
+        )?;
+
+        Ok(manifest)
+    }
+
+    fn compress_tar(&self, filename: String, files: &FileManifest) -> Result<SourcePackageFile> {
+        let extension = match self.compression {
+            DebCompression::Gzip | DebCompression::Xz(_) => self.compression.extension(),
+            DebCompression::Uncompressed | DebCompression::Zstandard(_) => {
+                return Err(DebianError::SourcePackageUnsupportedCompression);
+            }
+        };
+
+        let mut tar = vec![];
+        write_deb_tar(&mut tar, files, self.mtime())?;
+        let data = self.compression.compress(&mut std::io::Cursor::new(tar))?;
+
+        Ok(SourcePackageFile::new(
+            format!("{}{}", filename, extension),
+            data,
+        ))
+    }
+
+    /// Derive the `.dsc` control paragraph describing the given files.
+    fn dsc_paragraph(
+        &self,
+        native: bool,
+        version: &str,
+        files: &[SourcePackageFile],
+    ) -> Result<ControlParagraph<'static>> {
+        let general = self.control.general_paragraph();
+
+        let mut para = ControlParagraph::default();
+
+        para.set_field_from_string(
+            "Format".into(),
+            if native {
+                "3.0 (native)"
+            } else {
+                "3.0 (quilt)"
+            }
+            .into(),
+        );
+        para.set_field_from_string(
+            "Source".into(),
+            general.required_field_str("Source")?.to_string().into(),
+        );
+
+        let mut binaries = vec![];
+        let mut architectures = vec![];
+        let mut package_list = vec![];
+
+        for binary in self.control.binary_paragraphs() {
+            let package = binary.required_field_str("Package")?;
+            let package_architectures = binary
+                .iter_field_words("Architecture")
+                .ok_or_else(|| DebianError::ControlRequiredFieldMissing("Architecture".into()))?
+                .collect::<Vec<_>>();
+
+            for arch in &package_architectures {
+                if !architectures.contains(arch) {
+                    architectures.push(*arch);
+                }
+            }
+
+            package_list.push(format!(
+                "{} {} {} {} arch={}",
+                package,
+                binary.field_str("Package-Type").unwrap_or("deb"),
+                binary
+                    .field_str("Section")
+                    .or_else(|| general.field_str("Section"))
+                    .unwrap_or("misc"),
+                binary
+                    .field_str("Priority")
+                    .or_else(|| general.field_str("Priority"))
+                    .unwrap_or("optional"),
+                package_architectures.join(","),
+            ));
+            binaries.push(package);
+        }
+
+        para.set_field_from_string("Binary".into(), binaries.join(", ").into());
+        para.set_field_from_string("Architecture".into(), architectures.join(" ").into());
+        para.set_field_from_string("Version".into(), version.to_string().into());
+        para.set_field_from_string(
+            "Maintainer".into(),
+            general.required_field_str("Maintainer")?.to_string().into(),
+        );
+
+        for name in DSC_COPIED_FIELDS {
+            if let Some(field) = general.field(name) {
+                para.set_field(ControlField::new(
+                    field.name().to_string().into(),
+                    field.value_str().to_string().into(),
+                ));
+            }
+        }
+
+        para.set_field(ControlField::from_lines(
+            "Package-List".into(),
+            std::iter::once("".to_string()).chain(package_list),
+        ));
+
+        for (field, checksum) in [
+            ("Checksums-Sha1", ChecksumType::Sha1),
+            ("Checksums-Sha256", ChecksumType::Sha256),
+            ("Files", ChecksumType::Md5),
+        ] {
+            para.set_field(ControlField::from_lines(
+                field.into(),
+                std::iter::once("".to_string()).chain(files.iter().map(|file| {
+                    format!(
+                        "{} {} {}",
+                        file.digests.digest_from_checksum(checksum).digest_hex(),
+                        file.data.len(),
+                        file.filename
+                    )
+                })),
+            ));
+        }
+
+        Ok(para)
+    }
+
+    /// Build the source package.
+    pub fn build(&self) -> Result<SourcePackage> {
+        let source = self
+            .control
+            .general_paragraph()
+            .required_field_str("Source")?;
+
+        if self.control.binary_paragraphs().next().is_none() {
+            return Err(DebianError::SourcePackageNoBinaryPackages);
+        }
+
+        let entry = self
+            .changelog
+            .iter_entries()
+            .next()
+            .ok_or(DebianError::SourcePackageNoChangelogEntry)?;
+
+        if entry.package != source {
+            return Err(DebianError::SourcePackageChangelogSourceMismatch {
+                changelog: entry.package.to_string(),
+                control: source.to_string(),
+            });
+        }
+
+        let version_str = entry.version.to_string();
+        let version = PackageVersion::parse(&version_str)?;
+        let native = version.debian_revision().is_none();
+
+        // Filenames never contain the epoch.
+        let version_no_epoch = if let Some(revision) = version.debian_revision() {
+            format!("{}-{}", version.upstream_version(), revision)
+        } else {
+            version.upstream_version().to_string()
+        };
+
+        let debian_files = self.debian_directory(native)?;
+
+        let files = if native {
+            let root = PathBuf::from(format!("{}-{}", source, version_no_epoch));
+            let mut manifest = FileManifest::default();
+            for (path, entry) in self
+                .upstream_files
+                .iter_entries()
+                .chain(debian_files.iter_entries())
+            {
+                manifest.add_file_entry(root.join(path), entry.clone())?;
+            }
+
+            vec![self.compress_tar(format!("{}_{}.tar", source, version_no_epoch), &manifest)?]
+        } else {
+            let root = PathBuf::from(format!("{}-{}", source, version.upstream_version()));
+            let mut manifest = FileManifest::default();
+            for (path, entry) in self.upstream_files.iter_entries() {
+                manifest.add_file_entry(root.join(path), entry.clone())?;
+            }
+
+            vec![
+                self.compress_tar(
+                    format!("{}_{}.orig.tar", source, version.upstream_version()),
+                    &manifest,
+                )?,
+                self.compress_tar(
+                    format!("{}_{}.debian.tar", source, version_no_epoch),
+                    &debian_files,
+                )?,
+            ]
+        };
+
+        // Multiline fields are constructed with a leading empty line so they serialize
+        // correctly. Round trip through the serialized form so field accessors see the
+        // same values as readers of the written file.
+        let dsc = self.dsc_paragraph(native, &version_str, &files)?;
+        let dsc = DebianSourceControlFile::from_reader(std::io::Cursor::new(
+            dsc.to_string().into_bytes(),
+        ))?;
+
+        Ok(SourcePackage {
+            dsc,
+            dsc_filename: format!("{}_{}.dsc", source, version_no_epoch),
+            files,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::{
+            changelog::ChangelogEntry,
+            repository::builder::NO_SIGNING_KEY,
+            signing_key::{create_self_signed_key, signing_secret_key_params_builder},
+        },
+        chrono::TimeZone,
+        std::io::Read,
+    };
+
+    const CONTROL: &str = "Source: myapp\n\
+        Section: utils\n\
+        Maintainer: Me <me@example.com>\n\
+        Build-Depends: debhelper-compat (= 13)\n\
+        Standards-Version: 4.6.0\n\
+        \n\
+        Package: myapp\n\
+        Architecture: any\n\
+        Description: my app\n\
+        \n\
+        Package: myapp-data\n\
+        Architecture: all\n\
+        Section: misc\n\
+        Description: my app data\n";
+
+    fn changelog(version: &str) -> Changelog<'static> {
+        let mut changelog = Changelog::default();
+        changelog.add_entry(ChangelogEntry {
+            package: "myapp".into(),
+            version: version.to_string().into(),
+            distributions: vec!["unstable".into()],
+            urgency: "medium".into(),
+            details: "  * Initial release.".into(),
+            maintainer_name: "Me".into(),
+            maintainer_email: "me@example.com".into(),
+            date: chrono::Local.timestamp(1420000000, 0),
+        });
+
+        changelog
+    }
+
+    fn builder(version: &str) -> Result<SourcePackageBuilder<'static>> {
+        SourcePackageBuilder::new(
+            SourcePackageControlFile::parse_str(CONTROL)?,
+            changelog(version),
+        )
+        .set_mtime(Some(SystemTime::UNIX_EPOCH))
+        .add_upstream_file("src/main.c", b"int main() { return 0; }\n".to_vec())?
+        .add_debian_file("copyright", b"copyright\n".to_vec())
+    }
+
+    fn tar_paths(file: &SourcePackageFile) -> Result<Vec<String>> {
+        let mut data = vec![];
+        xz2::read::XzDecoder::new(std::io::Cursor::new(&file.data)).read_to_end(&mut data)?;
+
+        let mut archive = tar::Archive::new(std::io::Cursor::new(data));
+
+        let mut paths = vec![];
+        for entry in archive.entries()? {
+            paths.push(entry?.path()?.display().to_string());
+        }
+
+        Ok(paths)
+    }
+
+    #[test]
+    fn build_quilt() -> Result<()> {
+        let package = builder("1:1.0-1")?.build()?;
+
+        assert_eq!(package.dsc_filename(), "myapp_1.0-1.dsc");
+
+        let files = package.iter_files().collect::<Vec<_>>();
+        assert_eq!(files.len(), 2);
+        assert_eq!(files[0].filename, "myapp_1.0.orig.tar.xz");
+        assert_eq!(files[1].filename, "myapp_1.0-1.debian.tar.xz");
+
+        assert_eq!(
+            tar_paths(files[0])?,
+            vec![
+                "./",
+                "./myapp-1.0/",
+                "./myapp-1.0/src/",
+                "./myapp-1.0/src/main.c"
+            ]
+        );
+        assert_eq!(
+            tar_paths(files[1])?,
+            vec![
+                "./",
+                "./debian/",
+                "./debian/source/",
+                "./debian/changelog",
+                "./debian/control",
+                "./debian/copyright",
+                "./debian/rules",
+                "./debian/source/format",
+            ]
+        );
+
+        let dsc = package.dsc();
+        assert_eq!(dsc.format()?, "3.0 (quilt)");
+        assert_eq!(dsc.source()?, "myapp");
+        assert_eq!(dsc.version_str()?, "1:1.0-1");
+        assert_eq!(
+            dsc.binary().unwrap().collect::<Vec<_>>(),
+            vec!["myapp", "myapp-data"]
+        );
+        assert_eq!(
+            dsc.architecture().unwrap().collect::<Vec<_>>(),
+            vec!["any", "all"]
+        );
+        assert_eq!(dsc.standards_version()?, "4.6.0");
+        assert_eq!(
+            dsc.field_str("Build-Depends"),
+            Some("debhelper-compat (= 13)")
+        );
+
+        let packages = dsc.package_list().unwrap().collect::<Result<Vec<_>>>()?;
+        assert_eq!(packages.len(), 2);
+        assert_eq!(packages[0].section, "utils");
+        assert_eq!(packages[0].extra, vec!["arch=any"]);
+        assert_eq!(packages[1].section, "misc");
+
+        let entries = dsc
+            .checksums_sha256()
+            .unwrap()
+            .collect::<Result<Vec<_>>>()?;
+        assert_eq!(entries.len(), 2);
+        for (entry, file) in entries.iter().zip(files.iter()) {
+            assert_eq!(entry.filename, file.filename);
+            assert_eq!(entry.size, file.data.len() as u64);
+            assert_eq!(entry.digest, file.digests.sha256);
+        }
+
+        // Round trip through the serialized form.
+        let dsc = DebianSourceControlFile::from_reader(std::io::Cursor::new(
+            package.dsc_data(NO_SIGNING_KEY)?,
+        ))?;
+        assert_eq!(dsc.files()?.count(), 2);
+
+        Ok(())
+    }
+
+    #[test]
+    fn build_native() -> Result<()> {
+        let package = builder("1.0")?
+            .add_debian_file("rules", FileEntry::new_from_data(b"custom".to_vec(), true))?
+            .build()?;
+
+        assert_eq!(package.dsc_filename(), "myapp_1.0.dsc");
+        assert_eq!(package.dsc().format()?, "3.0 (native)");
+
+        let files = package.iter_files().collect::<Vec<_>>();
+        assert_eq!(files.len(), 1);
+        assert_eq!(files[0].filename, "myapp_1.0.tar.xz");
+        assert!(tar_paths(files[0])?.contains(&"./myapp-1.0/debian/rules".to_string()));
+
+        Ok(())
+    }
+
+    #[test]
+    fn build_errors() -> Result<()> {
+        assert!(matches!(
+            builder("1.0-1")?
+                .set_compression(DebCompression::Zstandard(3))
+                .build(),
+            Err(DebianError::SourcePackageUnsupportedCompression)
+        ));
+
+        assert!(matches!(
+            SourcePackageBuilder::new(
+                SourcePackageControlFile::parse_str(CONTROL)?,
+                Changelog::default()
+            )
+            .build(),
+            Err(DebianError::SourcePackageNoChangelogEntry)
+        ));
+
+        assert!(matches!(
+            SourcePackageBuilder::new(
+                SourcePackageControlFile::parse_str(
+                    "Source: myapp\nMaintainer: Me <me@example.com>\n"
+                )?,
+                changelog("1.0-1")
+            )
+            .build(),
+            Err(DebianError::SourcePackageNoBinaryPackages)
+        ));
+
+        let entry = changelog("1.0-1").iter_entries().next().unwrap().clone();
+        let mut mismatched = Changelog::default();
+        mismatched.add_entry(ChangelogEntry {
+            package: "otherapp".into(),
+            ..entry
+        });
+
+        assert!(matches!(
+            SourcePackageBuilder::new(SourcePackageControlFile::parse_str(CONTROL)?, mismatched)
+                .build(),
+            Err(DebianError::SourcePackageChangelogSourceMismatch { changelog, control })
+                if changelog == "otherapp" && control == "myapp"
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn signed_dsc() -> Result<()> {
+        let key = create_self_signed_key(
+            signing_secret_key_params_builder("Me <me@example.com>")
+                .build()
+                .unwrap(),
+            String::new,
+        )?
+        .0;
+
+        let package = builder("1.0-1")?.build()?;
+        let data = package.dsc_data(Some((&key, String::new)))?;
+
+        let dsc = DebianSourceControlFile::from_armored_reader(std::io::Cursor::new(data))?;
+        assert_eq!(dsc.source()?, "myapp");
+        dsc.signatures()
+            .expect("PGP signatures should have been parsed")
+            .verify(&key)?;
+
+        Ok(())
+    }
+}
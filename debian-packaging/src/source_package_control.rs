@@ -9,7 +9,7 @@ use {
         control::{ControlFile, ControlParagraph},
         error::{DebianError, Result},
     },
-    std::io::BufRead,
+    std::io::{BufRead, Write},
 };
 
 /// Represents a `debian/control` file.
@@ -62,4 +62,16 @@ impl<'a> SourcePackageControlFile<'a> {
     pub fn binary_paragraphs(&self) -> impl Iterator<Item = &ControlParagraph<'a>> {
         self.binaries.iter()
     }
+
+    /// Serialize the control file to a writer.
+    ///
+    /// The general paragraph is written first, followed by binary paragraphs.
+    pub fn write<W: Write>(&self, writer: &mut W) -> std::io::Result<()> {
+        for p in std::iter::once(&self.general).chain(self.binaries.iter()) {
+            p.write(writer)?;
+            writer.write_all(b"\n")?;
+        }
+
+        Ok(())
+    }
 }
@@ -3,15 +3,26 @@
 // file, You can obtain one at https://mozilla.org/MPL/2.0/.
 
 use {
+    async_trait::async_trait,
     clap::{Arg, ArgMatches, Command},
     debian_packaging::{
         error::DebianError,
+        io::DataResolver,
         repository::{
+            builder::{DebPackageReference, InMemoryDebFile, RepositoryBuilder, NO_SIGNING_KEY},
             copier::{RepositoryCopier, RepositoryCopierConfig},
-            PublishEvent,
+            filesystem::FilesystemRepositoryReader,
+            writer_from_str, PublishEvent,
         },
     },
-    std::sync::{Arc, Mutex},
+    futures::AsyncRead,
+    pgp::{Deserializable, SignedSecretKey},
+    std::{
+        collections::HashMap,
+        path::{Path, PathBuf},
+        pin::Pin,
+        sync::{Arc, Mutex},
+    },
     thiserror::Error,
 };
 
@@ -153,6 +164,26 @@ destination. This can result in packaging clients encountering missing
 files.
 ";
 
+const PUBLISH_DEBS_ABOUT: &str = "\
+Publish a Debian repository from local .deb files.
+
+Given paths to .deb files, this command creates a repository containing just
+those packages and writes it to the destination repository URL. The `pool`
+files, `Packages` indices, and `Release` file are written.
+
+If a PGP signing key is given, the `InRelease` and `Release.gpg` files are
+also written. The key must be an ASCII armored secret key. If the key is
+protected by a passphrase, it is read from the `DRT_SIGNING_KEY_PASSPHRASE`
+environment variable.
+
+The architectures advertised by the repository are derived from the
+`Architecture` field of the given packages.
+
+Indices are written to `dists/<distribution>`. Existing content in the
+destination is not taken into account: the published indices advertise only
+the given packages.
+";
+
 #[derive(Debug, Error)]
 pub enum DrtError {
     #[error("argument parsing error: {0:?}")]
@@ -167,6 +198,24 @@ pub enum DrtError {
     #[error("YAML error: {0:?}")]
     SerdeYaml(#[from] serde_yaml::Error),
 
+    #[error("PGP error: {0:?}")]
+    Pgp(#[from] pgp::errors::Error),
+
+    #[error("path is not a file: {0}")]
+    NotAFile(String),
+
+    #[error("path is not valid UTF-8: {0}")]
+    NonUtf8Path(String),
+
+    #[error("{path} duplicates package {package} {version} ({architecture}) from {existing}")]
+    DuplicatePackage {
+        path: String,
+        package: String,
+        version: String,
+        architecture: String,
+        existing: String,
+    },
+
     #[error("invalid sub-command: {0}")]
     InvalidSubCommand(String),
 }
@@ -205,6 +254,54 @@ pub async fn run_cli() -> Result<()> {
             ),
     );
 
+    let app = app.subcommand(
+        Command::new("publish-debs")
+            .about("Publish a Debian repository containing local .deb files")
+            .long_about(PUBLISH_DEBS_ABOUT)
+            .arg(
+                Arg::new("destination-url")
+                    .long("--destination-url")
+                    .takes_value(true)
+                    .required(true)
+                    .help("URL or path of the repository to write to"),
+            )
+            .arg(
+                Arg::new("distribution")
+                    .long("--distribution")
+                    .takes_value(true)
+                    .required(true)
+                    .help("Name (codename) of the distribution to publish"),
+            )
+            .arg(
+                Arg::new("suite")
+                    .long("--suite")
+                    .takes_value(true)
+                    .help("Suite of the distribution (defaults to the distribution name)"),
+            )
+            .arg(
+                Arg::new("component")
+                    .long("--component")
+                    .takes_value(true)
+                    .default_value("main")
+                    .help("Name of the component to publish packages in"),
+            )
+            .arg(
+                Arg::new("signing-key")
+                    .long("--signing-key")
+                    .takes_value(true)
+                    .allow_invalid_utf8(true)
+                    .help("Path to an ASCII armored PGP secret key to sign the repository with"),
+            )
+            .arg(
+                Arg::new("deb")
+                    .takes_value(true)
+                    .multiple_occurrences(true)
+                    .required(true)
+                    .allow_invalid_utf8(true)
+                    .help("Path to a .deb file to publish"),
+            ),
+    );
+
     let mut app =
         app.subcommand(Command::new("urls").about("Print documentation about repository URLs"));
 
@@ -212,6 +309,7 @@ pub async fn run_cli() -> Result<()> {
 
     match matches.subcommand() {
         Some(("copy-repository", args)) => command_copy_repository(args).await,
+        Some(("publish-debs", args)) => command_publish_debs(args).await,
         Some(("urls", _)) => {
             println!("{}", URLS_ABOUT);
             Ok(())
@@ -268,3 +366,147 @@ async fn command_copy_repository(args: &ArgMatches) -> Result<()> {
 
     Ok(())
 }
+
+async fn command_publish_debs(args: &ArgMatches) -> Result<()> {
+    let max_parallel_io = args.value_of_t::<usize>("max-parallel-io")?;
+
+    let destination_url = args
+        .value_of("destination-url")
+        .expect("destination-url argument is required");
+    let distribution = args
+        .value_of("distribution")
+        .expect("distribution argument is required");
+    let suite = args.value_of("suite").unwrap_or(distribution);
+    let component = args
+        .value_of("component")
+        .expect("component argument has default value");
+
+    let mut builder = RepositoryBuilder::new_recommended(
+        std::iter::empty::<&str>(),
+        [component].into_iter(),
+        suite,
+        distribution,
+    );
+
+    let mut resolver = LocalDebResolver::default();
+
+    // The builder silently replaces index entries having the same package, version, and
+    // architecture. Detect this so we don't publish whichever file came last.
+    let mut seen: HashMap<(String, String, String), PathBuf> = HashMap::new();
+
+    for path in args.values_of_os("deb").expect("deb argument is required") {
+        let path = std::fs::canonicalize(path)?;
+
+        let (parent, filename) = match (path.parent(), path.file_name()) {
+            (Some(parent), Some(filename)) => (parent, filename),
+            _ => return Err(DrtError::NotAFile(format!("{}", path.display()))),
+        };
+
+        // The filename becomes part of the pool path, so it must be a string.
+        let filename = filename
+            .to_str()
+            .ok_or_else(|| DrtError::NonUtf8Path(format!("{}", path.display())))?
+            .to_string();
+
+        let deb = InMemoryDebFile::new(filename.clone(), std::fs::read(&path)?);
+
+        let control = deb.control_file_for_packages_index()?;
+        let key = (
+            control.package()?.to_string(),
+            control.version_str()?.to_string(),
+            control.architecture()?.to_string(),
+        );
+
+        if let Some(existing) = seen.get(&key) {
+            return Err(DrtError::DuplicatePackage {
+                path: format!("{}", path.display()),
+                package: key.0,
+                version: key.1,
+                architecture: key.2,
+                existing: format!("{}", existing.display()),
+            });
+        }
+
+        builder.add_architecture(&key.2);
+
+        let pool_path = builder.add_binary_deb(component, &deb)?;
+        println!("{} -> {}", path.display(), pool_path);
+
+        resolver.files.insert(
+            pool_path,
+            (FilesystemRepositoryReader::new(parent), filename),
+        );
+        seen.insert(key, path);
+    }
+
+    let writer = writer_from_str(destination_url).await?;
+    let distribution_path = format!("dists/{}", distribution);
+
+    let cb = Some(|event: PublishEvent| {
+        if event.is_loggable() {
+            println!("{}", event);
+        }
+    });
+
+    if let Some(key_path) = args.value_of_os("signing-key") {
+        let key = load_signing_key(Path::new(key_path))?;
+
+        builder
+            .publish(
+                writer.as_ref(),
+                &resolver,
+                &distribution_path,
+                max_parallel_io,
+                &cb,
+                Some((&key, || {
+                    std::env::var("DRT_SIGNING_KEY_PASSPHRASE").unwrap_or_default()
+                })),
+            )
+            .await?;
+    } else {
+        builder
+            .publish(
+                writer.as_ref(),
+                &resolver,
+                &distribution_path,
+                max_parallel_io,
+                &cb,
+                NO_SIGNING_KEY,
+            )
+            .await?;
+    }
+
+    Ok(())
+}
+
+/// Resolves pool paths of published packages to their source `.deb` files.
+#[derive(Default)]
+struct LocalDebResolver {
+    /// Maps pool paths to a reader rooted at the directory of the source file and
+    /// the file's name in that directory.
+    files: HashMap<String, (FilesystemRepositoryReader, String)>,
+}
+
+#[async_trait]
+impl DataResolver for LocalDebResolver {
+    async fn get_path(
+        &self,
+        path: &str,
+    ) -> debian_packaging::error::Result<Pin<Box<dyn AsyncRead + Send>>> {
+        let (reader, filename) = self.files.get(path).ok_or_else(|| {
+            DebianError::RepositoryIoPath(
+                path.to_string(),
+                std::io::Error::new(std::io::ErrorKind::NotFound, "not a published .deb"),
+            )
+        })?;
+
+        reader.get_path(filename).await
+    }
+}
+
+fn load_signing_key(path: &Path) -> Result<SignedSecretKey> {
+    let f = std::fs::File::open(path)?;
+    let (key, _) = SignedSecretKey::from_armor_single(f)?;
+
+    Ok(key)
+}